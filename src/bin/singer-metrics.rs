@@ -1,25 +1,61 @@
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{stdin, BufReader, Read};
 
+use chrono::FixedOffset;
 use clap::Parser;
 use singer_metrics::cli::{Cli, Commands};
+use singer_metrics::graphite::Graphite;
+use singer_metrics::http_sink::HttpSink;
+use singer_metrics::json_format::Json;
 use singer_metrics::line_protocol::LineProtocol;
+use singer_metrics::metric::TimestampOptions;
 use singer_metrics::protocol_trait::ProtocolTrait;
+use singer_metrics::statsd::StatsD;
+
+/// Build a reader over the configured input file, or stdin if none was given
+fn reader(input: Option<std::path::PathBuf>) -> BufReader<Box<dyn Read>> {
+    let reader: Box<dyn Read> = match input {
+        Some(filename) => Box::new(File::open(filename).unwrap()),
+        None => Box::new(stdin()),
+    };
+    BufReader::new(reader)
+}
 
 fn main() {
     let cli = Cli::parse();
 
-    let protocol = match cli.command {
-        Commands::LineProtocol { precision } => LineProtocol::new(precision, None),
+    let timestamp_options = TimestampOptions {
+        format: cli.timestamp_format,
+        assumed_offset: FixedOffset::east_opt(cli.assumed_offset_minutes * 60)
+            .expect("Invalid assumed UTC offset"),
     };
+    let on_error = cli.on_error;
 
-    if let Some(filename) = cli.input {
-        // read from file
-        let file = File::open(filename).unwrap();
-        let reader = BufReader::new(file);
-        protocol.to_stdout(reader);
-    } else {
-        // read from stdin
-        protocol.pipe();
+    match cli.command {
+        Commands::LineProtocol {
+            precision,
+            normalize_units,
+        } => {
+            let protocol = LineProtocol::new(precision, None, normalize_units);
+            protocol.to_stdout(reader(cli.input), &timestamp_options, &on_error);
+        }
+        Commands::Write {
+            precision,
+            normalize_units,
+            host,
+            org,
+            bucket,
+            token,
+            max_batch,
+        } => {
+            let protocol = LineProtocol::new(precision.clone(), None, normalize_units);
+            let sink = HttpSink::new(host, org, bucket, token, precision, max_batch);
+            protocol
+                .to_sink(reader(cli.input), &sink, &timestamp_options, &on_error)
+                .unwrap();
+        }
+        Commands::Graphite => Graphite.to_stdout(reader(cli.input), &timestamp_options, &on_error),
+        Commands::StatsD => StatsD.to_stdout(reader(cli.input), &timestamp_options, &on_error),
+        Commands::Json => Json.to_stdout(reader(cli.input), &timestamp_options, &on_error),
     }
 }
@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use clap::{Parser, Subcommand};
 
-use crate::metric::Precision;
+use crate::metric::{OnError, Precision};
 
 #[derive(Parser)]
 #[command(author, version)]
@@ -13,6 +13,20 @@ pub struct Cli {
     /// The input file to read from
     #[arg(short, long, value_name = "FILE")]
     pub input: Option<PathBuf>,
+
+    /// The format used to parse a naive (offset-less) timestamp, tried
+    /// after RFC3339/ISO-8601 parsing fails
+    #[arg(long, value_name = "FORMAT", default_value = "%Y-%m-%d %H:%M:%S,%f")]
+    pub timestamp_format: String,
+
+    /// The UTC offset, in minutes, assumed for a naive timestamp that
+    /// doesn't carry its own offset
+    #[arg(long, value_name = "MINUTES", default_value_t = 0)]
+    pub assumed_offset_minutes: i32,
+
+    /// How to handle a line that fails to parse
+    #[arg(long, value_name = "ON_ERROR", default_value = "warn")]
+    pub on_error: OnError,
 }
 
 #[derive(Subcommand)]
@@ -22,5 +36,54 @@ pub enum Commands {
         /// The timestamp precision to use
         #[arg(short, long, value_name = "PRECISION", default_value = "ns")]
         precision: Precision,
+
+        /// Normalize timer values reported in seconds or nanoseconds to
+        /// milliseconds, emitting `unit=milliseconds` instead of the reported
+        /// unit as a tag; timers without a unit are left unchanged
+        #[arg(long)]
+        normalize_units: bool,
+    },
+
+    /// Write Singer metrics directly to an InfluxDB `/write` endpoint
+    Write {
+        /// The timestamp precision to use
+        #[arg(short, long, value_name = "PRECISION", default_value = "ns")]
+        precision: Precision,
+
+        /// Normalize timer values reported in seconds or nanoseconds to
+        /// milliseconds, emitting `unit=milliseconds` instead of the reported
+        /// unit as a tag; timers without a unit are left unchanged
+        #[arg(long)]
+        normalize_units: bool,
+
+        /// The InfluxDB host, e.g. `http://localhost:8086`
+        #[arg(long, value_name = "HOST")]
+        host: String,
+
+        /// The InfluxDB organization
+        #[arg(long, value_name = "ORG")]
+        org: String,
+
+        /// The InfluxDB bucket (or database, for InfluxDB 1.x)
+        #[arg(long, value_name = "BUCKET")]
+        bucket: String,
+
+        /// The InfluxDB API token
+        #[arg(long, value_name = "TOKEN", env = "INFLUXDB_TOKEN")]
+        token: String,
+
+        /// The maximum number of lines to batch before flushing a write
+        #[arg(long, value_name = "N", default_value_t = 5000)]
+        max_batch: usize,
     },
+
+    /// Convert Singer metrics to Graphite plaintext protocol
+    Graphite,
+
+    /// Convert Singer metrics to StatsD protocol
+    #[command(name = "statsd")]
+    StatsD,
+
+    /// Convert Singer metrics to newline-delimited JSON
+    Json,
 }
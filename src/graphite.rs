@@ -0,0 +1,155 @@
+use serde_json::Value;
+
+use crate::metric::{Measurement, Point, Tags, Unit};
+use crate::protocol_trait::ProtocolTrait;
+
+/// Graphite plaintext protocol
+///
+/// `https://graphite.readthedocs.io/en/latest/feeding-carbon.html#the-plaintext-protocol`
+#[derive(Default)]
+pub struct Graphite;
+
+impl ProtocolTrait for Graphite {
+    /// Dump a measurement to a string
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement to dump
+    ///
+    /// # Returns
+    ///
+    /// A string in the form `metric.path value timestamp`, where `path` is
+    /// built by appending each tag as a `key.value` segment
+    fn dump(&self, measurement: &Measurement) -> String {
+        let timestamp = measurement.timestamp.timestamp();
+        match &measurement.point {
+            Point::Timer {
+                metric,
+                value,
+                tags,
+                unit,
+            } => {
+                format!(
+                    "{} {} {}",
+                    format_path(metric, tags, *unit),
+                    value,
+                    timestamp
+                )
+            }
+            Point::Counter {
+                metric,
+                value,
+                tags,
+                unit,
+            } => {
+                format!(
+                    "{} {} {}",
+                    format_path(metric, tags, *unit),
+                    value,
+                    timestamp
+                )
+            }
+        }
+    }
+}
+
+/// Build a dotted Graphite metric path out of a metric name, its tags and
+/// its unit, if known
+fn format_path(metric: &str, tags: &Tags, unit: Option<Unit>) -> String {
+    let mut parts = vec![metric.to_string()];
+    parts.extend(format_tag_parts(tags, None));
+    if let Some(unit) = unit {
+        parts.push(format!("unit.{}", unit.as_str()));
+    }
+    parts.join(".")
+}
+
+/// Flatten a tag map into `key.value` dotted path segments, recursing into
+/// nested objects and expanding arrays by index
+fn format_tag_parts(tags: &Tags, prefix: Option<&str>) -> Vec<String> {
+    tags.iter()
+        .flat_map(|(k, v)| {
+            let key = match prefix {
+                Some(prefix) => format!("{prefix}_{k}"),
+                None => k.clone(),
+            };
+            format_tag_value(&key, v)
+        })
+        .collect()
+}
+
+fn format_tag_value(key: &str, value: &Value) -> Vec<String> {
+    match value {
+        Value::Object(obj) => format_tag_parts(obj, Some(key)),
+        Value::Null => vec![],
+        Value::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .flat_map(|(i, v)| format_tag_value(&format!("{key}_{i}"), v))
+            .collect(),
+        other => vec![format!("{key}.{}", other.to_string().replace('"', ""))],
+    }
+}
+
+#[test]
+fn test_graphite_timer() {
+    use chrono::{DateTime, Utc};
+
+    let protocol = Graphite;
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Timer {
+            metric: "test".to_string(),
+            value: 1.23,
+            tags: Tags::from_iter(vec![("tag1".to_string(), Value::String("value1".to_string()))]),
+            unit: None,
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    assert_eq!(line, "test.tag1.value1 1.23 1624579200");
+}
+
+#[test]
+fn test_graphite_counter_no_tags() {
+    use chrono::{DateTime, Utc};
+
+    let protocol = Graphite;
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Counter {
+            metric: "test".to_string(),
+            value: 42,
+            tags: Tags::new(),
+            unit: None,
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    assert_eq!(line, "test 42 1624579200");
+}
+
+#[test]
+fn test_graphite_unit() {
+    use chrono::{DateTime, Utc};
+
+    let protocol = Graphite;
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Timer {
+            metric: "test".to_string(),
+            value: 1.23,
+            tags: Tags::new(),
+            unit: Some(Unit::Seconds),
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    assert_eq!(line, "test.unit.seconds 1.23 1624579200");
+}
@@ -0,0 +1,174 @@
+use crate::metric::Precision;
+
+/// A sink that writes InfluxDB line protocol to an InfluxDB `/write` endpoint
+/// over HTTP, batching lines instead of sending one request per measurement.
+///
+/// `https://docs.influxdata.com/influxdb/v2.6/api/#operation/PostWrite`
+pub struct HttpSink {
+    /// The InfluxDB host, e.g. `http://localhost:8086`
+    pub host: String,
+
+    /// The InfluxDB organization
+    pub org: String,
+
+    /// The InfluxDB bucket (or database, for InfluxDB 1.x)
+    pub bucket: String,
+
+    /// The InfluxDB API token
+    pub token: String,
+
+    /// The timestamp precision of the lines being written
+    pub precision: Precision,
+
+    /// The maximum number of lines to accumulate before flushing a write
+    pub max_batch: usize,
+
+    /// The HTTP client used for every flush, reused across batches to keep
+    /// connections alive
+    client: reqwest::blocking::Client,
+}
+
+impl HttpSink {
+    /// Create a new HttpSink
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The InfluxDB host, e.g. `http://localhost:8086`
+    /// * `org` - The InfluxDB organization
+    /// * `bucket` - The InfluxDB bucket (or database, for InfluxDB 1.x)
+    /// * `token` - The InfluxDB API token
+    /// * `precision` - The timestamp precision of the lines being written
+    /// * `max_batch` - The maximum number of lines to accumulate before
+    ///   flushing a write
+    pub fn new(
+        host: String,
+        org: String,
+        bucket: String,
+        token: String,
+        precision: Precision,
+        max_batch: usize,
+    ) -> Self {
+        HttpSink {
+            host,
+            org,
+            bucket,
+            token,
+            precision,
+            max_batch,
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+
+    /// The `/write` endpoint URL, including the `org`, `bucket` and
+    /// `precision` query parameters
+    fn url(&self) -> String {
+        format!(
+            "{}/api/v2/write?org={}&bucket={}&precision={}",
+            self.host,
+            self.org,
+            self.bucket,
+            self.precision.as_query_str(),
+        )
+    }
+
+    /// POST a batch of already-formatted lines, joined with `\n`, to the
+    /// `/write` endpoint
+    fn flush(&self, batch: &[String]) -> Result<(), String> {
+        let body = batch.join("\n");
+        let response = self
+            .client
+            .post(self.url())
+            .bearer_auth(&self.token)
+            .body(body)
+            .send()
+            .map_err(|e| format!("Failed to write to InfluxDB: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            return Err(format!("InfluxDB write failed with {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+
+    /// Write lines to InfluxDB, accumulating up to `max_batch` lines per
+    /// request
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The formatted lines to write
+    pub fn write_lines(&self, lines: impl Iterator<Item = String>) -> Result<(), String> {
+        for batch in batch_lines(lines, self.max_batch) {
+            self.flush(&batch)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lazily split an iterator of lines into batches of at most `max_batch`
+/// lines, with any remainder smaller than `max_batch` as the final batch
+///
+/// Each batch is pulled from `lines` only as the returned iterator is
+/// advanced, so a caller that flushes a batch as soon as it's yielded still
+/// streams instead of buffering the whole input.
+fn batch_lines(
+    mut lines: impl Iterator<Item = String>,
+    max_batch: usize,
+) -> impl Iterator<Item = Vec<String>> {
+    // `take(0)` yields nothing without even touching `lines`, which would
+    // silently drop every line instead of sending one per batch
+    let max_batch = max_batch.max(1);
+
+    std::iter::from_fn(move || {
+        let batch: Vec<String> = lines.by_ref().take(max_batch).collect();
+        if batch.is_empty() {
+            None
+        } else {
+            Some(batch)
+        }
+    })
+}
+
+#[test]
+fn test_batch_lines_splits_on_boundary() {
+    let lines = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let batches: Vec<Vec<String>> = batch_lines(lines.into_iter(), 2).collect();
+
+    assert_eq!(
+        batches,
+        vec![vec!["a".to_string(), "b".to_string()], vec!["c".to_string()]]
+    );
+}
+
+#[test]
+fn test_batch_lines_empty() {
+    let batches: Vec<Vec<String>> = batch_lines(std::iter::empty(), 2).collect();
+    assert!(batches.is_empty());
+}
+
+#[test]
+fn test_batch_lines_zero_max_batch_sends_one_line_per_batch() {
+    let lines = vec!["a".to_string(), "b".to_string()];
+    let batches: Vec<Vec<String>> = batch_lines(lines.into_iter(), 0).collect();
+
+    assert_eq!(batches, vec![vec!["a".to_string()], vec!["b".to_string()]]);
+}
+
+#[test]
+fn test_url() {
+    let sink = HttpSink::new(
+        "http://localhost:8086".to_string(),
+        "myorg".to_string(),
+        "mybucket".to_string(),
+        "mytoken".to_string(),
+        Precision::Milliseconds,
+        5000,
+    );
+
+    assert_eq!(
+        sink.url(),
+        "http://localhost:8086/api/v2/write?org=myorg&bucket=mybucket&precision=ms"
+    );
+}
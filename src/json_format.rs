@@ -0,0 +1,47 @@
+use crate::metric::Measurement;
+use crate::protocol_trait::ProtocolTrait;
+
+/// Newline-delimited JSON
+#[derive(Default)]
+pub struct Json;
+
+impl ProtocolTrait for Json {
+    /// Dump a measurement to a string
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement to dump
+    ///
+    /// # Returns
+    ///
+    /// The parsed measurement, echoed back out as a single line of JSON
+    fn dump(&self, measurement: &Measurement) -> String {
+        serde_json::to_string(measurement).expect("Failed to serialize measurement")
+    }
+}
+
+#[test]
+fn test_json_timer() {
+    use chrono::{DateTime, Utc};
+    use serde_json::Value;
+
+    use crate::metric::{Point, Tags};
+
+    let protocol = Json;
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Timer {
+            metric: "test".to_string(),
+            value: 1.23,
+            tags: Tags::from_iter(vec![("tag1".to_string(), Value::String("value1".to_string()))]),
+            unit: None,
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    let parsed: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(parsed["point"]["metric"], "test");
+    assert_eq!(parsed["point"]["value"], 1.23);
+}
@@ -0,0 +1,8 @@
+pub mod cli;
+pub mod graphite;
+pub mod http_sink;
+pub mod json_format;
+pub mod line_protocol;
+pub mod metric;
+pub mod protocol_trait;
+pub mod statsd;
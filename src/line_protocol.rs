@@ -1,7 +1,7 @@
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 
-use crate::metric::{Measurement, Point, Precision, Tags};
+use crate::metric::{Measurement, Point, Precision, Tags, Unit};
 use crate::protocol_trait::ProtocolTrait;
 
 /// InfluxDB line protocol
@@ -14,6 +14,11 @@ pub struct LineProtocol {
 
     /// The timestamp precision to use when formatting timestamps
     pub precision: Precision,
+
+    /// Normalize timer values reported in seconds or nanoseconds to
+    /// milliseconds, emitting `unit=milliseconds` instead of the reported
+    /// unit as a tag; timers without a unit are left unchanged
+    pub normalize_units: bool,
 }
 
 impl LineProtocol {
@@ -23,6 +28,8 @@ impl LineProtocol {
     /// * `precision` - The precision to use when formatting timestamps
     /// * `extra_tags` - An optional map of extra tags to add to each
     ///   measurement
+    /// * `normalize_units` - Normalize timer values reported in seconds or
+    ///   nanoseconds to milliseconds; timers without a unit are left unchanged
     ///
     /// # Returns
     ///
@@ -39,15 +46,16 @@ impl LineProtocol {
     /// let mut extra_tags = Tags::new();
     /// extra_tags.insert("host".to_string(), Value::String("localhost".to_string()));
     ///
-    /// let protocol = LineProtocol::new(Precision::default(), Some(extra_tags));
+    /// let protocol = LineProtocol::new(Precision::default(), Some(extra_tags), false);
     ///
     /// assert!(matches!(protocol.precision, Precision::Nanoseconds));
     /// assert_eq!(protocol.extra_tags.len(), 1);
     /// ```
-    pub fn new(precision: Precision, extra_tags: Option<Tags>) -> Self {
+    pub fn new(precision: Precision, extra_tags: Option<Tags>, normalize_units: bool) -> Self {
         LineProtocol {
             precision,
             extra_tags: extra_tags.unwrap_or_default(),
+            normalize_units,
         }
     }
 }
@@ -79,6 +87,7 @@ impl ProtocolTrait for LineProtocol {
     ///       metric: "test".to_string(),
     ///       value: 1.0,
     ///       tags: Tags::from_iter(vec![("tag1".to_string(), Value::String("value1".to_string()))]),
+    ///       unit: None,
     ///    },
     ///    timestamp: Utc::now(),
     /// });
@@ -90,11 +99,13 @@ impl ProtocolTrait for LineProtocol {
                 metric,
                 value,
                 tags,
+                unit,
             } => {
+                let (value, unit) = self.resolve_timer_value(*value, *unit);
                 format!(
                     "{}{} value={} {}",
                     metric,
-                    format_tags(tags, &self.extra_tags),
+                    format_tags(tags, &self.extra_tags, unit),
                     value,
                     format_datetime(&measurement.timestamp, &self.precision),
                 )
@@ -103,11 +114,12 @@ impl ProtocolTrait for LineProtocol {
                 metric,
                 value,
                 tags,
+                unit,
             } => {
                 format!(
                     "{}{} value={} {}",
                     metric,
-                    format_tags(tags, &self.extra_tags),
+                    format_tags(tags, &self.extra_tags, *unit),
                     value,
                     format_datetime(&measurement.timestamp, &self.precision),
                 )
@@ -116,6 +128,26 @@ impl ProtocolTrait for LineProtocol {
     }
 }
 
+impl LineProtocol {
+    /// Resolve the value and unit tag to emit for a timer
+    ///
+    /// When `normalize_units` is set and the timer's unit is a time unit,
+    /// the value is converted to milliseconds and `unit` is reported as
+    /// `Milliseconds`. Otherwise the value and unit are passed through
+    /// unchanged.
+    fn resolve_timer_value(&self, value: f64, unit: Option<Unit>) -> (f64, Option<Unit>) {
+        if !self.normalize_units {
+            return (value, unit);
+        }
+
+        match unit {
+            Some(Unit::Seconds) => (value * 1_000.0, Some(Unit::Milliseconds)),
+            Some(Unit::Nanoseconds) => (value / 1_000_000.0, Some(Unit::Milliseconds)),
+            other => (value, other),
+        }
+    }
+}
+
 fn _add_key_prefix(key: &str, prefix: Option<&str>) -> String {
     if let Some(prefix) = prefix {
         return format!("{}__{}", prefix, key);
@@ -159,9 +191,16 @@ fn format_tag_value(key: &str, value: &Value) -> String {
     }
 }
 
-/// Format a set of tags into a string
-fn format_tags(tags: &Tags, extra_tags: &Tags) -> String {
-    let mut tags_string = format_map_iter(tags.iter().chain(extra_tags.iter()), None);
+/// Format a set of tags into a string, optionally appending a `unit` tag
+fn format_tags(tags: &Tags, extra_tags: &Tags, unit: Option<Unit>) -> String {
+    let unit_tag = unit.map(|unit| ("unit".to_string(), Value::String(unit.as_str().to_string())));
+
+    let mut tags_string = format_map_iter(
+        tags.iter()
+            .chain(extra_tags.iter())
+            .chain(unit_tag.iter().map(|(k, v)| (k, v))),
+        None,
+    );
     if !tags_string.is_empty() {
         tags_string.insert(0, ',');
     }
@@ -193,6 +232,7 @@ fn test_line_protocol() {
                 ("tag1".to_string(), Value::String("value1".to_string())),
                 ("tag2".to_string(), Value::Number(2.into())),
             ]),
+            unit: None,
         },
         timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
             .unwrap()
@@ -216,6 +256,7 @@ fn test_line_protocol_empty_tags() {
             metric: "test".to_string(),
             value: 1.23,
             tags: Tags::new(),
+            unit: None,
         },
         timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
             .unwrap()
@@ -229,13 +270,14 @@ fn test_line_protocol_empty_tags() {
 fn test_line_protocol_ms_precision() {
     use chrono::Utc;
 
-    let protocol = LineProtocol::new(Precision::Milliseconds, None);
+    let protocol = LineProtocol::new(Precision::Milliseconds, None, false);
 
     let line = protocol.dump(&Measurement {
         point: Point::Timer {
             metric: "test".to_string(),
             value: 1.23,
             tags: Tags::new(),
+            unit: None,
         },
         timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
             .unwrap()
@@ -256,6 +298,7 @@ fn test_line_protocol_extra_tags() {
             ("tag3".to_string(), Value::String("value3".to_string())),
             ("tag4".to_string(), Value::Number(4.into())),
         ])),
+        false,
     );
 
     let line = protocol.dump(&Measurement {
@@ -266,6 +309,7 @@ fn test_line_protocol_extra_tags() {
                 ("tag1".to_string(), Value::String("value1".to_string())),
                 ("tag2".to_string(), Value::Number(2.into())),
             ]),
+            unit: None,
         },
         timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
             .unwrap()
@@ -303,6 +347,7 @@ fn test_line_protocol_nested_tags() {
                     ),
                 ),
             ]),
+            unit: None,
         },
         timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
             .unwrap()
@@ -319,7 +364,7 @@ fn test_line_protocol_nested_tags() {
 fn test_escape_whitespace() {
     use chrono::Utc;
 
-    let protocol = LineProtocol::new(Precision::Milliseconds, None);
+    let protocol = LineProtocol::new(Precision::Milliseconds, None, false);
 
     let line = protocol.dump(&Measurement {
         point: Point::Counter {
@@ -335,6 +380,7 @@ fn test_escape_whitespace() {
                     Value::String("Value With Spaces".to_string()),
                 ),
             ]),
+            unit: None,
         },
         timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
             .unwrap()
@@ -347,3 +393,49 @@ fn test_escape_whitespace() {
          1624579200000"
     );
 }
+
+#[test]
+fn test_line_protocol_unit_tag() {
+    use chrono::Utc;
+
+    use crate::metric::Unit;
+
+    let protocol = LineProtocol::new(Precision::Seconds, None, false);
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Timer {
+            metric: "test".to_string(),
+            value: 1.23,
+            tags: Tags::new(),
+            unit: Some(Unit::Seconds),
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    assert_eq!(line, "test,unit=seconds value=1.23 1624579200");
+}
+
+#[test]
+fn test_line_protocol_normalize_units() {
+    use chrono::Utc;
+
+    use crate::metric::Unit;
+
+    let protocol = LineProtocol::new(Precision::Seconds, None, true);
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Timer {
+            metric: "test".to_string(),
+            value: 1.0,
+            tags: Tags::new(),
+            unit: Some(Unit::Seconds),
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    assert_eq!(line, "test,unit=milliseconds value=1000 1624579200");
+}
@@ -1,7 +1,8 @@
+use std::fmt;
 use std::io::{BufRead, BufReader, Read};
 
 use chrono::prelude::{TimeZone, Utc};
-use chrono::DateTime;
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
 use lazy_static::lazy_static;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -9,6 +10,49 @@ use serde_json::{Map, Value};
 
 pub type Tags = Map<String, Value>;
 
+/// An error encountered while parsing a singer metric log line
+#[derive(Debug)]
+pub enum ParseError {
+    /// Failed to read a line from the input
+    Io(std::io::Error),
+
+    /// The line didn't match the expected singer metric log format
+    NoMatch(String),
+
+    /// The timestamp couldn't be parsed
+    InvalidTimestamp(String),
+
+    /// The metric JSON payload couldn't be parsed
+    InvalidJson(serde_json::Error),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "I/O error: {}", e),
+            ParseError::NoMatch(line) => write!(f, "Invalid line: {}", line),
+            ParseError::InvalidTimestamp(msg) => write!(f, "Invalid timestamp: {}", msg),
+            ParseError::InvalidJson(e) => write!(f, "Invalid JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// How to handle a line that fails to parse
+#[derive(Clone, Debug, Default, clap::ValueEnum)]
+pub enum OnError {
+    /// Silently drop the line and continue
+    Skip,
+
+    /// Write a diagnostic to stderr and continue
+    #[default]
+    Warn,
+
+    /// Stop processing and exit with a nonzero status
+    Fail,
+}
+
 lazy_static! {
     static ref SINGER_METRIC_PATTERN: Regex =
         Regex::new(r"^(?P<timestamp>.+?)?\s*?INFO METRIC: (?P<metric_json>.*)$").unwrap();
@@ -26,6 +70,10 @@ pub enum Point {
 
         /// The metric tags
         tags: Tags,
+
+        /// The unit the value is expressed in, if known
+        #[serde(default)]
+        unit: Option<Unit>,
     },
     Counter {
         /// The metric name
@@ -36,9 +84,84 @@ pub enum Point {
 
         /// The metric tags
         tags: Tags,
+
+        /// The unit the value is expressed in, if known
+        #[serde(default)]
+        unit: Option<Unit>,
     },
 }
 
+impl Point {
+    /// The unit this point's value is expressed in, if known
+    pub fn unit(&self) -> Option<Unit> {
+        match self {
+            Point::Timer { unit, .. } | Point::Counter { unit, .. } => *unit,
+        }
+    }
+
+    /// Fill in `unit` from a `unit` tag when it wasn't set at the top level,
+    /// removing the tag so it isn't also emitted as a regular dimension
+    fn resolve_unit_from_tags(&mut self) {
+        let (tags, unit) = match self {
+            Point::Timer { tags, unit, .. } | Point::Counter { tags, unit, .. } => (tags, unit),
+        };
+
+        if unit.is_some() {
+            return;
+        }
+
+        let parsed = tags
+            .get("unit")
+            .and_then(|v| v.as_str())
+            .and_then(|s| Unit::from_string(s).ok());
+        if let Some(parsed) = parsed {
+            tags.remove("unit");
+            *unit = Some(parsed);
+        }
+    }
+}
+
+/// The unit a metric value is expressed in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Unit {
+    #[serde(alias = "s")]
+    Seconds,
+
+    #[serde(alias = "ms")]
+    Milliseconds,
+
+    #[serde(alias = "ns")]
+    Nanoseconds,
+
+    Bytes,
+    Count,
+}
+
+impl Unit {
+    pub fn from_string(s: &str) -> Result<Self, String> {
+        match s {
+            "seconds" | "s" => Ok(Unit::Seconds),
+            "milliseconds" | "ms" => Ok(Unit::Milliseconds),
+            "nanoseconds" | "ns" => Ok(Unit::Nanoseconds),
+            "bytes" => Ok(Unit::Bytes),
+            "count" => Ok(Unit::Count),
+            _ => Err(format!("Invalid unit: {}", s)),
+        }
+    }
+
+    /// The line-protocol tag value for this unit
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Unit::Seconds => "seconds",
+            Unit::Milliseconds => "milliseconds",
+            Unit::Nanoseconds => "nanoseconds",
+            Unit::Bytes => "bytes",
+            Unit::Count => "count",
+        }
+    }
+}
+
 /// The timestamp precision to use
 #[derive(Clone, Debug, Default, clap::ValueEnum)]
 pub enum Precision {
@@ -66,9 +189,62 @@ impl Precision {
             _ => Err(format!("Invalid precision: {}", s)),
         }
     }
+
+    /// The query-string value InfluxDB expects for this precision
+    pub fn as_query_str(&self) -> &'static str {
+        match self {
+            Precision::Nanoseconds => "ns",
+            Precision::Microseconds => "us",
+            Precision::Milliseconds => "ms",
+            Precision::Seconds => "s",
+        }
+    }
+}
+
+/// Options controlling how timestamps are parsed
+#[derive(Clone, Debug)]
+pub struct TimestampOptions {
+    /// The format used to parse a naive (offset-less) timestamp, tried
+    /// after RFC3339/ISO-8601 parsing fails
+    pub format: String,
+
+    /// The UTC offset assumed for a naive timestamp that parses
+    /// successfully against `format`
+    pub assumed_offset: FixedOffset,
+}
+
+impl Default for TimestampOptions {
+    fn default() -> Self {
+        TimestampOptions {
+            format: "%Y-%m-%d %H:%M:%S,%f".to_string(),
+            assumed_offset: FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+}
+
+/// Parse a timestamp, first as RFC3339/ISO-8601 (which carries its own
+/// offset), falling back to a naive timestamp in `options.format` assumed
+/// to be in `options.assumed_offset`
+fn parse_timestamp(s: &str, options: &TimestampOptions) -> Result<DateTime<Utc>, ParseError> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(s, &options.format)
+        .map_err(|e| ParseError::InvalidTimestamp(e.to_string()))?;
+
+    options
+        .assumed_offset
+        .from_local_datetime(&naive)
+        .single()
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| {
+            ParseError::InvalidTimestamp(format!("Ambiguous or invalid local timestamp: {}", s))
+        })
 }
 
 /// A measurement from a singer metric log
+#[derive(Serialize)]
 pub struct Measurement {
     /// The measurement point
     pub point: Point,
@@ -83,35 +259,57 @@ impl Measurement {
     /// # Arguments
     ///
     /// * `line` - The line to parse
+    /// * `timestamp_options` - Options controlling how a naive timestamp is
+    ///   parsed and localized; a missing timestamp still defaults to
+    ///   `Utc::now()`
     ///
     /// # Returns
     ///
     /// A measurement result
-    fn from_singer_metric_line(line: &str) -> Result<Self, String> {
+    fn from_singer_metric_line(
+        line: &str,
+        timestamp_options: &TimestampOptions,
+    ) -> Result<Self, ParseError> {
         let caps = SINGER_METRIC_PATTERN
             .captures(line)
-            .ok_or_else(|| format!("Invalid line: {}", line))?;
+            .ok_or_else(|| ParseError::NoMatch(line.to_string()))?;
 
         let timestamp = caps.name("timestamp").map_or(Ok(Utc::now()), |ts| {
-            Utc.datetime_from_str(ts.as_str(), "%Y-%m-%d %H:%M:%S,%f")
-                .map_err(|e| format!("Invalid timestamp: {}", e))
+            parse_timestamp(ts.as_str(), timestamp_options)
         })?;
 
         let json_string = caps
             .name("metric_json")
-            .ok_or_else(|| format!("No measurement JSON found in line: {}", line))?
+            .ok_or_else(|| ParseError::NoMatch(line.to_string()))?
             .as_str();
-        let point: Point = serde_json::from_str(json_string).expect("Invalid JSON found in line");
+        let mut point: Point =
+            serde_json::from_str(json_string).map_err(ParseError::InvalidJson)?;
+        point.resolve_unit_from_tags();
 
         let measurement: Self = Self { point, timestamp };
         Ok(measurement)
     }
 
     /// Read a file of singer metric lines into an iterator of measurements
-    pub fn read(buffer: BufReader<impl Read>) -> impl Iterator<Item = Result<Self, String>> {
-        buffer
-            .lines()
-            .map(|line| Self::from_singer_metric_line(&line.unwrap()))
+    ///
+    /// Unlike a single bad line aborting the whole conversion, each line is
+    /// parsed independently; a malformed line surfaces as an `Err` without
+    /// stopping iteration, so callers can decide how to handle it (see
+    /// `OnError`).
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The reader to read lines from
+    /// * `timestamp_options` - Options controlling how naive timestamps are
+    ///   parsed and localized
+    pub fn read(
+        buffer: BufReader<impl Read>,
+        timestamp_options: TimestampOptions,
+    ) -> impl Iterator<Item = Result<Self, ParseError>> {
+        buffer.lines().map(move |line| {
+            let line = line.map_err(ParseError::Io)?;
+            Self::from_singer_metric_line(&line, &timestamp_options)
+        })
     }
 }
 
@@ -119,7 +317,8 @@ impl Measurement {
 fn test_from_singer_metric_line() {
     let line = "2020-10-01 00:00:00,000 INFO METRIC: {\"metric_type\": \"timer\", \"metric\": \
                 \"test\", \"value\": 1.0, \"tags\": {\"tag1\": \"value1\"}}";
-    let measurement = Measurement::from_singer_metric_line(line).unwrap();
+    let measurement =
+        Measurement::from_singer_metric_line(line, &TimestampOptions::default()).unwrap();
 
     assert_eq!(
         measurement
@@ -129,7 +328,7 @@ fn test_from_singer_metric_line() {
         "2020-10-01 00:00:00,000000000"
     );
     assert!(
-        matches!(measurement.point, Point::Timer { metric, value, tags } if metric == "test" && value == 1.0 && tags.len() == 1)
+        matches!(measurement.point, Point::Timer { metric, value, tags, .. } if metric == "test" && value == 1.0 && tags.len() == 1)
     );
 }
 
@@ -137,10 +336,78 @@ fn test_from_singer_metric_line() {
 fn test_from_singer_metric_line_no_timestamp() {
     let line = "INFO METRIC: {\"metric_type\": \"timer\", \"metric\": \"test\", \"value\": 1.0, \
                 \"tags\": {\"tag1\": \"value1\"}}";
-    let measurement = Measurement::from_singer_metric_line(line).unwrap();
+    let measurement =
+        Measurement::from_singer_metric_line(line, &TimestampOptions::default()).unwrap();
 
     assert!(measurement.timestamp > Utc::now() - chrono::Duration::seconds(1));
     assert!(
-        matches!(measurement.point, Point::Timer { metric, value, tags } if metric == "test" && value == 1.0 && tags.len() == 1)
+        matches!(measurement.point, Point::Timer { metric, value, tags, .. } if metric == "test" && value == 1.0 && tags.len() == 1)
+    );
+}
+
+#[test]
+fn test_from_singer_metric_line_rfc3339() {
+    let line = "2020-10-01T00:00:00+02:00 INFO METRIC: {\"metric_type\": \"timer\", \"metric\": \
+                \"test\", \"value\": 1.0, \"tags\": {}}";
+    let measurement =
+        Measurement::from_singer_metric_line(line, &TimestampOptions::default()).unwrap();
+
+    assert_eq!(
+        measurement.timestamp,
+        DateTime::parse_from_rfc3339("2020-09-30T22:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    );
+}
+
+#[test]
+fn test_from_singer_metric_line_assumed_offset() {
+    let line = "2020-10-01 00:00:00,000 INFO METRIC: {\"metric_type\": \"timer\", \"metric\": \
+                \"test\", \"value\": 1.0, \"tags\": {}}";
+    let options = TimestampOptions {
+        assumed_offset: FixedOffset::east_opt(2 * 3600).unwrap(),
+        ..TimestampOptions::default()
+    };
+    let measurement = Measurement::from_singer_metric_line(line, &options).unwrap();
+
+    assert_eq!(
+        measurement.timestamp,
+        DateTime::parse_from_rfc3339("2020-09-30T22:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    );
+}
+
+#[test]
+fn test_from_singer_metric_line_unit_tag() {
+    let line = "2020-10-01 00:00:00,000 INFO METRIC: {\"metric_type\": \"timer\", \"metric\": \
+                \"test\", \"value\": 1.0, \"tags\": {\"unit\": \"ms\"}}";
+    let measurement =
+        Measurement::from_singer_metric_line(line, &TimestampOptions::default()).unwrap();
+
+    assert_eq!(measurement.point.unit(), Some(Unit::Milliseconds));
+    assert!(matches!(measurement.point, Point::Timer { tags, .. } if !tags.contains_key("unit")));
+}
+
+#[test]
+fn test_from_singer_metric_line_top_level_unit_abbreviation() {
+    let line = "2020-10-01 00:00:00,000 INFO METRIC: {\"metric_type\": \"timer\", \"metric\": \
+                \"test\", \"value\": 1.0, \"tags\": {}, \"unit\": \"ms\"}";
+    let measurement =
+        Measurement::from_singer_metric_line(line, &TimestampOptions::default()).unwrap();
+
+    assert_eq!(measurement.point.unit(), Some(Unit::Milliseconds));
+}
+
+#[test]
+fn test_from_singer_metric_line_unknown_unit_tag_preserved() {
+    let line = "2020-10-01 00:00:00,000 INFO METRIC: {\"metric_type\": \"timer\", \"metric\": \
+                \"test\", \"value\": 1.0, \"tags\": {\"unit\": \"minutes\"}}";
+    let measurement =
+        Measurement::from_singer_metric_line(line, &TimestampOptions::default()).unwrap();
+
+    assert_eq!(measurement.point.unit(), None);
+    assert!(
+        matches!(measurement.point, Point::Timer { tags, .. } if tags.get("unit") == Some(&Value::String("minutes".to_string())))
     );
 }
@@ -1,6 +1,7 @@
 use std::io::{BufReader, BufWriter, Read, Write};
 
-use crate::metric::Measurement;
+use crate::http_sink::HttpSink;
+use crate::metric::{Measurement, OnError, TimestampOptions};
 
 pub trait ProtocolTrait {
     /// Dump a measurement to a string
@@ -20,10 +21,30 @@ pub trait ProtocolTrait {
     ///
     /// * `reader` - The reader to read from
     /// * `writer` - The writer to write to
-    fn convert(&self, reader: BufReader<impl Read>, mut writer: BufWriter<impl Write>) {
-        Measurement::read(reader)
-            .map(|measurement| self.dump(&measurement.unwrap()))
-            .for_each(|line| writeln!(&mut writer, "{}", line).unwrap());
+    /// * `timestamp_options` - Options controlling how naive timestamps are
+    ///   parsed and localized
+    /// * `on_error` - How to handle a line that fails to parse
+    fn convert(
+        &self,
+        reader: BufReader<impl Read>,
+        mut writer: BufWriter<impl Write>,
+        timestamp_options: &TimestampOptions,
+        on_error: &OnError,
+    ) {
+        for (line_number, result) in Measurement::read(reader, timestamp_options.clone()).enumerate()
+        {
+            match result {
+                Ok(measurement) => {
+                    writeln!(&mut writer, "{}", self.dump(&measurement)).unwrap();
+                }
+                Err(err) => {
+                    if let ErrorAction::Stop = handle_parse_error(line_number + 1, &err, on_error) {
+                        writer.flush().unwrap();
+                        std::process::exit(1);
+                    }
+                }
+            }
+        }
     }
 
     /// Convert input from reader to stdout
@@ -31,15 +52,144 @@ pub trait ProtocolTrait {
     /// # Arguments
     ///
     /// * `reader` - The reader to read from
-    fn to_stdout(&self, reader: BufReader<impl Read>) {
+    /// * `timestamp_options` - Options controlling how naive timestamps are
+    ///   parsed and localized
+    /// * `on_error` - How to handle a line that fails to parse
+    fn to_stdout(
+        &self,
+        reader: BufReader<impl Read>,
+        timestamp_options: &TimestampOptions,
+        on_error: &OnError,
+    ) {
         let stdout = std::io::stdout();
         let writer = BufWriter::new(stdout.lock());
-        self.convert(reader, writer)
+        self.convert(reader, writer, timestamp_options, on_error)
     }
 
     /// Pipe from stdin to stdout
-    fn pipe(&self) {
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp_options` - Options controlling how naive timestamps are
+    ///   parsed and localized
+    /// * `on_error` - How to handle a line that fails to parse
+    fn pipe(&self, timestamp_options: &TimestampOptions, on_error: &OnError) {
         let stdin = std::io::stdin();
-        self.to_stdout(BufReader::new(stdin.lock()));
+        self.to_stdout(BufReader::new(stdin.lock()), timestamp_options, on_error);
     }
+
+    /// Convert input from reader and write the result to an `HttpSink`
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader to read from
+    /// * `sink` - The sink to write batched lines to
+    /// * `timestamp_options` - Options controlling how naive timestamps are
+    ///   parsed and localized
+    /// * `on_error` - How to handle a line that fails to parse
+    fn to_sink(
+        &self,
+        reader: BufReader<impl Read>,
+        sink: &HttpSink,
+        timestamp_options: &TimestampOptions,
+        on_error: &OnError,
+    ) -> Result<(), String> {
+        let mut failed = false;
+        let lines = Measurement::read(reader, timestamp_options.clone())
+            .enumerate()
+            .map_while(|(line_number, result)| {
+                if failed {
+                    return None;
+                }
+                match result {
+                    Ok(measurement) => Some(Some(self.dump(&measurement))),
+                    Err(err) => match handle_parse_error(line_number + 1, &err, on_error) {
+                        ErrorAction::Continue => Some(None),
+                        ErrorAction::Stop => {
+                            failed = true;
+                            None
+                        }
+                    },
+                }
+            })
+            .flatten();
+        sink.write_lines(lines)?;
+
+        if failed {
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
+
+/// What a writer/sink loop should do after a line fails to parse, per the
+/// configured `OnError` policy
+enum ErrorAction {
+    Continue,
+    Stop,
+}
+
+/// Apply the configured `OnError` policy to a line that failed to parse,
+/// without terminating the process, so the caller can flush already
+/// converted output first
+fn handle_parse_error(
+    line_number: usize,
+    err: &crate::metric::ParseError,
+    on_error: &OnError,
+) -> ErrorAction {
+    match on_error {
+        OnError::Skip => ErrorAction::Continue,
+        OnError::Warn => {
+            eprintln!("Skipping line {}: {}", line_number, err);
+            ErrorAction::Continue
+        }
+        OnError::Fail => {
+            eprintln!("Line {}: {}", line_number, err);
+            ErrorAction::Stop
+        }
+    }
+}
+
+#[test]
+fn test_convert_skip_continues_past_bad_line() {
+    use std::io::Cursor;
+
+    use crate::json_format::Json;
+
+    let input = "not a metric line\n\
+                 2021-06-25T00:00:00Z INFO METRIC: {\"metric_type\": \"counter\", \"metric\": \
+                 \"good\", \"value\": 1, \"tags\": {}}\n";
+    let mut output = Vec::new();
+    Json.convert(
+        BufReader::new(Cursor::new(input)),
+        BufWriter::new(&mut output),
+        &TimestampOptions::default(),
+        &OnError::Skip,
+    );
+
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.lines().count(), 1);
+    assert!(output.contains("\"good\""));
+}
+
+#[test]
+fn test_convert_warn_continues_past_bad_line() {
+    use std::io::Cursor;
+
+    use crate::json_format::Json;
+
+    let input = "not a metric line\n\
+                 2021-06-25T00:00:00Z INFO METRIC: {\"metric_type\": \"counter\", \"metric\": \
+                 \"good\", \"value\": 1, \"tags\": {}}\n";
+    let mut output = Vec::new();
+    Json.convert(
+        BufReader::new(Cursor::new(input)),
+        BufWriter::new(&mut output),
+        &TimestampOptions::default(),
+        &OnError::Warn,
+    );
+
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.lines().count(), 1);
+    assert!(output.contains("\"good\""));
 }
@@ -0,0 +1,147 @@
+use serde_json::Value;
+
+use crate::metric::{Measurement, Point, Tags, Unit};
+use crate::protocol_trait::ProtocolTrait;
+
+/// StatsD protocol
+///
+/// `https://github.com/statsd/statsd/blob/master/docs/metric_types.md`
+#[derive(Default)]
+pub struct StatsD;
+
+impl ProtocolTrait for StatsD {
+    /// Dump a measurement to a string
+    ///
+    /// # Arguments
+    ///
+    /// * `measurement` - The measurement to dump
+    ///
+    /// # Returns
+    ///
+    /// A string in the form `metric:value|ms` for timers or `metric:value|c`
+    /// for counters, with tags appended as `|#k:v,k:v`
+    fn dump(&self, measurement: &Measurement) -> String {
+        match &measurement.point {
+            Point::Timer {
+                metric,
+                value,
+                tags,
+                unit,
+            } => {
+                format!("{metric}:{value}|ms{}", format_tags(tags, *unit))
+            }
+            Point::Counter {
+                metric,
+                value,
+                tags,
+                unit,
+            } => {
+                format!("{metric}:{value}|c{}", format_tags(tags, *unit))
+            }
+        }
+    }
+}
+
+/// Format a set of tags, and the unit if known, as a StatsD `|#k:v,k:v` tag
+/// suffix, or an empty string if there are no tags and no unit
+fn format_tags(tags: &Tags, unit: Option<Unit>) -> String {
+    let mut pairs = format_tag_pairs(tags, None);
+    if let Some(unit) = unit {
+        pairs.push(format!("unit:{}", unit.as_str()));
+    }
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("|#{}", pairs.join(","))
+    }
+}
+
+/// Flatten a tag map into `key:value` pairs, recursing into nested objects
+/// and expanding arrays by index
+fn format_tag_pairs(tags: &Tags, prefix: Option<&str>) -> Vec<String> {
+    tags.iter()
+        .flat_map(|(k, v)| {
+            let key = match prefix {
+                Some(prefix) => format!("{prefix}_{k}"),
+                None => k.clone(),
+            };
+            format_tag_value(&key, v)
+        })
+        .collect()
+}
+
+fn format_tag_value(key: &str, value: &Value) -> Vec<String> {
+    match value {
+        Value::Object(obj) => format_tag_pairs(obj, Some(key)),
+        Value::Null => vec![],
+        Value::Array(arr) => arr
+            .iter()
+            .enumerate()
+            .flat_map(|(i, v)| format_tag_value(&format!("{key}_{i}"), v))
+            .collect(),
+        other => vec![format!("{key}:{}", other.to_string().replace('"', ""))],
+    }
+}
+
+#[test]
+fn test_statsd_timer() {
+    use chrono::{DateTime, Utc};
+
+    let protocol = StatsD;
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Timer {
+            metric: "test".to_string(),
+            value: 1.23,
+            tags: Tags::from_iter(vec![("tag1".to_string(), Value::String("value1".to_string()))]),
+            unit: None,
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    assert_eq!(line, "test:1.23|ms|#tag1:value1");
+}
+
+#[test]
+fn test_statsd_counter_no_tags() {
+    use chrono::{DateTime, Utc};
+
+    let protocol = StatsD;
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Counter {
+            metric: "test".to_string(),
+            value: 42,
+            tags: Tags::new(),
+            unit: None,
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    assert_eq!(line, "test:42|c");
+}
+
+#[test]
+fn test_statsd_unit() {
+    use chrono::{DateTime, Utc};
+
+    let protocol = StatsD;
+
+    let line = protocol.dump(&Measurement {
+        point: Point::Timer {
+            metric: "test".to_string(),
+            value: 1.23,
+            tags: Tags::new(),
+            unit: Some(Unit::Seconds),
+        },
+        timestamp: DateTime::parse_from_rfc3339("2021-06-25T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    });
+
+    assert_eq!(line, "test:1.23|ms|#unit:seconds");
+}